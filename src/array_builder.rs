@@ -0,0 +1,281 @@
+use std::io;
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+use crate::object_builder::ObjectBuilder;
+use crate::path;
+use crate::stream::StreamSink;
+
+/// Builds a `serde_json::Value::Array` through a small, chainable API.
+///
+/// Construct with [`ArrayBuilder::new`] (or via [`crate::array`]), fill it in
+/// with [`ArrayBuilder::push`]/[`ArrayBuilder::object`]/[`ArrayBuilder::objects`],
+/// then finish with [`ArrayBuilder::unwrap`] to get the underlying `Value`.
+///
+/// Like [`ObjectBuilder`], a builder either collects into a `Value` tree or
+/// streams straight to an `io::Write` sink via the crate-internal
+/// [`ArrayBuilder::new_stream`] -- see [`ObjectBuilder`]'s docs for how the
+/// two modes relate. [`ArrayBuilder::write_objects`] is the public entry
+/// point that uses streaming mode.
+pub struct ArrayBuilder<'w> {
+    storage: Storage<'w>,
+}
+
+enum Storage<'w> {
+    Tree(Value),
+    Stream(StreamSink<'w>),
+}
+
+impl ArrayBuilder<'static> {
+    pub fn new() -> Self {
+        ArrayBuilder {
+            storage: Storage::Tree(Value::Array(Vec::new())),
+        }
+    }
+}
+
+impl<'w> ArrayBuilder<'w> {
+    /// Start streaming a `[ ... ]` straight to `writer` as elements are
+    /// pushed, rather than accumulating a `Value` tree.
+    pub(crate) fn new_stream(writer: &'w mut dyn io::Write) -> Self {
+        let mut sink = StreamSink::new(writer);
+        sink.raw(b"[");
+        ArrayBuilder {
+            storage: Storage::Stream(sink),
+        }
+    }
+
+    /// Close a streaming builder's `]` and surface the first write error
+    /// hit while building, if any. Only meaningful paired with
+    /// [`ArrayBuilder::new_stream`].
+    pub(crate) fn finish_stream(self) -> io::Result<()> {
+        match self.storage {
+            Storage::Stream(mut sink) => {
+                if !sink.has_error() {
+                    sink.raw(b"]");
+                }
+                sink.into_error().map_or(Ok(()), Err)
+            }
+            Storage::Tree(_) => unreachable!("finish_stream called on a tree-mode ArrayBuilder"),
+        }
+    }
+
+    /// Push `value` onto the array.
+    pub fn push<V: Into<Value>>(&mut self, value: V) -> &mut Self {
+        self.push_json(value.into())
+    }
+
+    /// Push an already-built `Value` onto the array.
+    pub fn push_json(&mut self, value: Value) -> &mut Self {
+        match &mut self.storage {
+            Storage::Tree(tree) => {
+                tree.as_array_mut()
+                    .expect("ArrayBuilder always wraps a JSON array")
+                    .push(value);
+            }
+            Storage::Stream(sink) => {
+                sink.separator();
+                sink.json(&value);
+            }
+        }
+        self
+    }
+
+    /// Build a nested object element with a sub-builder.
+    pub fn object<F: FnOnce(&mut ObjectBuilder)>(&mut self, f: F) -> &mut Self {
+        if let Storage::Stream(sink) = &mut self.storage {
+            if sink.has_error() {
+                return self;
+            }
+            sink.separator();
+            let mut nested = ObjectBuilder::new_stream(sink.writer_mut());
+            f(&mut nested);
+            if let Err(e) = nested.finish_stream() {
+                sink.set_error(e);
+            }
+            return self;
+        }
+        let mut nested = ObjectBuilder::new();
+        f(&mut nested);
+        self.push_json(nested.unwrap())
+    }
+
+    /// Build a nested array element with a sub-builder.
+    pub fn array<F: FnOnce(&mut ArrayBuilder)>(&mut self, f: F) -> &mut Self {
+        if let Storage::Stream(sink) = &mut self.storage {
+            if sink.has_error() {
+                return self;
+            }
+            sink.separator();
+            let mut nested = ArrayBuilder::new_stream(sink.writer_mut());
+            f(&mut nested);
+            if let Err(e) = nested.finish_stream() {
+                sink.set_error(e);
+            }
+            return self;
+        }
+        let mut nested = ArrayBuilder::new();
+        f(&mut nested);
+        self.push_json(nested.unwrap())
+    }
+
+    /// Build one object element per item yielded by `iter`. Streams
+    /// directly to a writer when this builder is itself streaming (see
+    /// [`ArrayBuilder::write_objects`]).
+    pub fn objects<'a, T: 'a, I, F>(&mut self, iter: I, f: F) -> &mut Self
+    where
+        I: IntoIterator<Item = &'a T>,
+        F: Fn(&'a T, &mut ObjectBuilder),
+    {
+        for item in iter {
+            self.object(|nested| f(item, nested));
+        }
+        self
+    }
+
+    /// Set the value addressed by a JSONPath expression (e.g.
+    /// `$[0].price` or `$[*].price`), auto-vivifying missing intermediate
+    /// objects/arrays. A wildcard segment applies `value` to every node it
+    /// currently matches.
+    pub fn set_path<V: Into<Value>>(&mut self, path: &str, value: V) -> Result<&mut Self> {
+        let segments = path::parse(path)?;
+        let value = value.into();
+        if segments.is_empty() && !value.is_array() {
+            return Err(Error::PathMismatch(
+                "root path '$' must be set to an array on an ArrayBuilder".to_string(),
+            ));
+        }
+        path::set(self.tree_mut("set_path"), &segments, value)?;
+        Ok(self)
+    }
+
+    /// Read the first value addressed by a JSONPath expression, if any.
+    pub fn get_path(&self, path: &str) -> Result<Option<Value>> {
+        let segments = path::parse(path)?;
+        Ok(path::query(self.tree("get_path"), &segments)
+            .first()
+            .map(|v| (*v).clone()))
+    }
+
+    /// Whether a JSONPath expression matches at least one existing node.
+    pub fn has_path(&self, path: &str) -> Result<bool> {
+        let segments = path::parse(path)?;
+        Ok(!path::query(self.tree("has_path"), &segments).is_empty())
+    }
+
+    /// Finish building and return the underlying `Value`.
+    pub fn unwrap(self) -> Value {
+        match self.storage {
+            Storage::Tree(value) => value,
+            Storage::Stream(_) => {
+                unreachable!("ArrayBuilder::unwrap called on a builder streaming to a writer")
+            }
+        }
+    }
+
+    /// Finish building and write the already-built array straight to `w`
+    /// through `serde_json`'s writer-based serializer, skipping the
+    /// intermediate `String` buffer. Like [`ObjectBuilder::write_to`], the
+    /// full `Value` assembled by the preceding `push`/`object`/`array`
+    /// calls is resident in memory before this runs -- for a batch too
+    /// large to build up front, use [`ArrayBuilder::write_objects`] to
+    /// stream one element's fields at a time instead.
+    pub fn write_to<W: io::Write>(self, w: &mut W) -> io::Result<()> {
+        match self.storage {
+            Storage::Tree(value) => serde_json::to_writer(w, &value).map_err(io::Error::from),
+            Storage::Stream(_) => {
+                unreachable!("ArrayBuilder::write_to called on a builder already streaming")
+            }
+        }
+    }
+
+    /// Write `[ {..}, {..}, ... ]` to `w`, streaming each object element's
+    /// fields straight to `w` as they're set rather than building a
+    /// `Value` for the row -- or the array -- first. Use this in place of
+    /// [`ArrayBuilder::objects`] when the batch is too large to keep fully
+    /// resident, e.g. streaming a million-row array or NDJSON records.
+    pub fn write_objects<'a, T: 'a, I, F, W>(w: &mut W, iter: I, f: F) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'a T>,
+        F: Fn(&'a T, &mut ObjectBuilder),
+        W: io::Write,
+    {
+        let mut builder = ArrayBuilder::new_stream(w);
+        builder.objects(iter, f);
+        builder.finish_stream()
+    }
+
+    fn tree(&self, method: &str) -> &Value {
+        match &self.storage {
+            Storage::Tree(value) => value,
+            Storage::Stream(_) => unreachable!(
+                "ArrayBuilder::{} called on a builder streaming to a writer",
+                method
+            ),
+        }
+    }
+
+    fn tree_mut(&mut self, method: &str) -> &mut Value {
+        match &mut self.storage {
+            Storage::Tree(value) => value,
+            Storage::Stream(_) => unreachable!(
+                "ArrayBuilder::{} called on a builder streaming to a writer",
+                method
+            ),
+        }
+    }
+}
+
+impl Default for ArrayBuilder<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_path_root_rejects_non_array_value() {
+        let mut builder = ArrayBuilder::new();
+        assert!(builder.set_path("$", 5).is_err());
+
+        // The builder must still be usable afterwards.
+        builder.push(1);
+        assert_eq!(builder.unwrap(), serde_json::json!([1]));
+    }
+
+    #[test]
+    fn set_path_root_accepts_array_value() {
+        let mut builder = ArrayBuilder::new();
+        builder.set_path("$", serde_json::json!([1, 2])).unwrap();
+        assert_eq!(builder.unwrap(), serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn get_path_and_has_path_find_existing_and_missing_nodes() {
+        let mut builder = ArrayBuilder::new();
+        builder.push(1);
+        builder.object(|obj| {
+            obj.set("name", "Ada");
+        });
+
+        assert_eq!(
+            builder.get_path("$[0]").unwrap(),
+            Some(serde_json::json!(1))
+        );
+        assert!(builder.has_path("$[0]").unwrap());
+
+        assert_eq!(builder.get_path("$[5]").unwrap(), None);
+        assert!(!builder.has_path("$[5]").unwrap());
+
+        assert_eq!(
+            builder.get_path("$[1].name").unwrap(),
+            Some(serde_json::json!("Ada"))
+        );
+        assert!(builder.has_path("$[1].name").unwrap());
+        assert!(!builder.has_path("$[1].missing").unwrap());
+    }
+}
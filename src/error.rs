@@ -0,0 +1,24 @@
+use std::fmt;
+
+/// Errors produced while building or walking a JSON tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A JSONPath expression could not be parsed.
+    InvalidPath(String),
+    /// A JSONPath expression is syntactically valid but cannot be applied to
+    /// the value it is being walked against (e.g. indexing into a string).
+    PathMismatch(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidPath(msg) => write!(f, "invalid JSONPath: {}", msg),
+            Error::PathMismatch(msg) => write!(f, "JSONPath mismatch: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,31 @@
+//! jsonway is a small builder API for assembling `serde_json::Value` trees
+//! without hand-writing `serde::Serialize` impls.
+
+mod array_builder;
+mod error;
+mod object_builder;
+mod path;
+pub mod schema;
+mod serializer;
+mod stream;
+
+pub use array_builder::ArrayBuilder;
+pub use error::{Error, Result};
+pub use object_builder::ObjectBuilder;
+pub use serializer::Serializer;
+
+/// Build a JSON object with a closure. Finish with `.unwrap()` to get the
+/// underlying `serde_json::Value`.
+pub fn object<F: FnOnce(&mut ObjectBuilder)>(f: F) -> ObjectBuilder<'static> {
+    let mut builder = ObjectBuilder::new();
+    f(&mut builder);
+    builder
+}
+
+/// Build a JSON array with a closure. Finish with `.unwrap()` to get the
+/// underlying `serde_json::Value`.
+pub fn array<F: FnOnce(&mut ArrayBuilder)>(f: F) -> ArrayBuilder<'static> {
+    let mut builder = ArrayBuilder::new();
+    f(&mut builder);
+    builder
+}
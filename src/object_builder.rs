@@ -0,0 +1,262 @@
+use std::io;
+
+use serde_json::{Map, Value};
+
+use crate::array_builder::ArrayBuilder;
+use crate::error::{Error, Result};
+use crate::path;
+use crate::stream::StreamSink;
+
+/// Builds a `serde_json::Value::Object` through a small, chainable API.
+///
+/// Construct with [`ObjectBuilder::new`] (or via [`crate::object`]), fill it
+/// in with [`ObjectBuilder::set`]/[`ObjectBuilder::object`]/[`ObjectBuilder::array`],
+/// then finish with [`ObjectBuilder::unwrap`] to get the underlying `Value`.
+///
+/// Internally a builder either collects into a `Value` tree (the mode
+/// described above) or streams straight to an `io::Write` sink as each
+/// field is set, one token at a time, never holding a `Value` for
+/// anything but the one leaf currently being written. The streaming mode
+/// is entered via the crate-internal [`ObjectBuilder::new_stream`], used by
+/// [`crate::Serializer::serialize_to_writer`] and
+/// [`ArrayBuilder::write_objects`]; the `Value`-returning methods
+/// (`unwrap`, `get_path`, `has_path`, `set_path`) only make sense in the
+/// collecting mode and panic if called on a streaming builder.
+pub struct ObjectBuilder<'w> {
+    storage: Storage<'w>,
+}
+
+enum Storage<'w> {
+    Tree(Value),
+    Stream(StreamSink<'w>),
+}
+
+impl ObjectBuilder<'static> {
+    pub fn new() -> Self {
+        ObjectBuilder {
+            storage: Storage::Tree(Value::Object(Map::new())),
+        }
+    }
+}
+
+impl<'w> ObjectBuilder<'w> {
+    /// Start streaming a `{ ... }` straight to `writer` as fields are set,
+    /// rather than accumulating a `Value` tree.
+    pub(crate) fn new_stream(writer: &'w mut dyn io::Write) -> Self {
+        let mut sink = StreamSink::new(writer);
+        sink.raw(b"{");
+        ObjectBuilder {
+            storage: Storage::Stream(sink),
+        }
+    }
+
+    /// Close a streaming builder's `}` and surface the first write error
+    /// hit while building, if any. Only meaningful paired with
+    /// [`ObjectBuilder::new_stream`].
+    pub(crate) fn finish_stream(self) -> io::Result<()> {
+        match self.storage {
+            Storage::Stream(mut sink) => {
+                if !sink.has_error() {
+                    sink.raw(b"}");
+                }
+                sink.into_error().map_or(Ok(()), Err)
+            }
+            Storage::Tree(_) => unreachable!("finish_stream called on a tree-mode ObjectBuilder"),
+        }
+    }
+
+    /// Set `key` to `value`, overwriting any existing entry.
+    pub fn set<K: Into<String>, V: Into<Value>>(&mut self, key: K, value: V) -> &mut Self {
+        let key = key.into();
+        let value = value.into();
+        match &mut self.storage {
+            Storage::Tree(tree) => {
+                tree.as_object_mut()
+                    .expect("ObjectBuilder always wraps a JSON object")
+                    .insert(key, value);
+            }
+            Storage::Stream(sink) => {
+                sink.begin_entry(&key);
+                sink.json(&value);
+            }
+        }
+        self
+    }
+
+    /// Build a nested object at `key` with a sub-builder.
+    pub fn object<K: Into<String>, F: FnOnce(&mut ObjectBuilder)>(
+        &mut self,
+        key: K,
+        f: F,
+    ) -> &mut Self {
+        if let Storage::Stream(sink) = &mut self.storage {
+            if sink.has_error() {
+                return self;
+            }
+            sink.begin_entry(&key.into());
+            let mut nested = ObjectBuilder::new_stream(sink.writer_mut());
+            f(&mut nested);
+            if let Err(e) = nested.finish_stream() {
+                sink.set_error(e);
+            }
+            return self;
+        }
+        let mut nested = ObjectBuilder::new();
+        f(&mut nested);
+        self.set(key, nested.unwrap())
+    }
+
+    /// Build a nested array at `key` with a sub-builder.
+    pub fn array<K: Into<String>, F: FnOnce(&mut ArrayBuilder)>(
+        &mut self,
+        key: K,
+        f: F,
+    ) -> &mut Self {
+        if let Storage::Stream(sink) = &mut self.storage {
+            if sink.has_error() {
+                return self;
+            }
+            sink.begin_entry(&key.into());
+            let mut nested = ArrayBuilder::new_stream(sink.writer_mut());
+            f(&mut nested);
+            if let Err(e) = nested.finish_stream() {
+                sink.set_error(e);
+            }
+            return self;
+        }
+        let mut nested = ArrayBuilder::new();
+        f(&mut nested);
+        self.set(key, nested.unwrap())
+    }
+
+    /// Set the value addressed by a JSONPath expression (e.g.
+    /// `$.user.addresses[0].city`), auto-vivifying missing intermediate
+    /// objects/arrays. A wildcard segment (`[*]`/`.*`) applies `value` to
+    /// every node it currently matches.
+    pub fn set_path<V: Into<Value>>(&mut self, path: &str, value: V) -> Result<&mut Self> {
+        let segments = path::parse(path)?;
+        let value = value.into();
+        if segments.is_empty() && !value.is_object() {
+            return Err(Error::PathMismatch(
+                "root path '$' must be set to an object on an ObjectBuilder".to_string(),
+            ));
+        }
+        path::set(self.tree_mut("set_path"), &segments, value)?;
+        Ok(self)
+    }
+
+    /// Read the first value addressed by a JSONPath expression, if any.
+    pub fn get_path(&self, path: &str) -> Result<Option<Value>> {
+        let segments = path::parse(path)?;
+        Ok(path::query(self.tree("get_path"), &segments)
+            .first()
+            .map(|v| (*v).clone()))
+    }
+
+    /// Whether a JSONPath expression matches at least one existing node.
+    pub fn has_path(&self, path: &str) -> Result<bool> {
+        let segments = path::parse(path)?;
+        Ok(!path::query(self.tree("has_path"), &segments).is_empty())
+    }
+
+    /// Finish building and return the underlying `Value`.
+    pub fn unwrap(self) -> Value {
+        match self.storage {
+            Storage::Tree(value) => value,
+            Storage::Stream(_) => {
+                unreachable!("ObjectBuilder::unwrap called on a builder streaming to a writer")
+            }
+        }
+    }
+
+    /// Finish building and write the already-built value straight to `w`
+    /// through `serde_json`'s writer-based serializer, skipping the
+    /// intermediate `String` buffer. The `Value` assembled by the
+    /// preceding `set`/`object`/`array` calls is fully resident in memory
+    /// at this point -- builders constructed through
+    /// [`crate::Serializer::serialize_to_writer`] or
+    /// [`ArrayBuilder::write_objects`] never reach this path, since they
+    /// stream their tokens straight to a writer as fields are set instead.
+    pub fn write_to<W: io::Write>(self, w: &mut W) -> io::Result<()> {
+        match self.storage {
+            Storage::Tree(value) => serde_json::to_writer(w, &value).map_err(io::Error::from),
+            Storage::Stream(_) => {
+                unreachable!("ObjectBuilder::write_to called on a builder already streaming")
+            }
+        }
+    }
+
+    fn tree(&self, method: &str) -> &Value {
+        match &self.storage {
+            Storage::Tree(value) => value,
+            Storage::Stream(_) => unreachable!(
+                "ObjectBuilder::{} called on a builder streaming to a writer",
+                method
+            ),
+        }
+    }
+
+    fn tree_mut(&mut self, method: &str) -> &mut Value {
+        match &mut self.storage {
+            Storage::Tree(value) => value,
+            Storage::Stream(_) => unreachable!(
+                "ObjectBuilder::{} called on a builder streaming to a writer",
+                method
+            ),
+        }
+    }
+}
+
+impl Default for ObjectBuilder<'static> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_path_root_rejects_non_object_value() {
+        let mut builder = ObjectBuilder::new();
+        assert!(builder.set_path("$", 5).is_err());
+
+        // The builder must still be usable afterwards.
+        builder.set("x", 1);
+        assert_eq!(builder.unwrap(), serde_json::json!({"x": 1}));
+    }
+
+    #[test]
+    fn set_path_root_accepts_object_value() {
+        let mut builder = ObjectBuilder::new();
+        builder.set_path("$", serde_json::json!({"a": 1})).unwrap();
+        assert_eq!(builder.unwrap(), serde_json::json!({"a": 1}));
+    }
+
+    #[test]
+    fn get_path_and_has_path_find_existing_and_missing_nodes() {
+        let mut builder = ObjectBuilder::new();
+        builder.set("name", "Ada");
+        builder.array("tags", |arr| {
+            arr.push("admin");
+            arr.push("staff");
+        });
+
+        assert_eq!(
+            builder.get_path("$.name").unwrap(),
+            Some(serde_json::json!("Ada"))
+        );
+        assert!(builder.has_path("$.name").unwrap());
+
+        assert_eq!(builder.get_path("$.missing").unwrap(), None);
+        assert!(!builder.has_path("$.missing").unwrap());
+
+        assert_eq!(
+            builder.get_path("$.tags[*]").unwrap(),
+            Some(serde_json::json!("admin"))
+        );
+        assert!(builder.has_path("$.tags[*]").unwrap());
+        assert!(!builder.has_path("$.tags[5]").unwrap());
+    }
+}
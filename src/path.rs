@@ -0,0 +1,303 @@
+//! A minimal JSONPath parser and evaluator used by the builders' `*_path`
+//! methods. Only the subset needed for addressing and mutating a
+//! `serde_json::Value` tree is implemented: the root `$`, dotted and
+//! bracketed child access, numeric indices, wildcards, and slices.
+
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Segment {
+    Child(String),
+    Index(usize),
+    Wildcard,
+    Slice(Option<isize>, Option<isize>),
+}
+
+/// Parse a JSONPath expression such as `$.user.addresses[0].city` or
+/// `$.items[*].price` into a sequence of segments.
+pub(crate) fn parse(path: &str) -> Result<Vec<Segment>> {
+    let mut chars = path.chars().peekable();
+    if chars.next() != Some('$') {
+        return Err(Error::InvalidPath(format!(
+            "path must start with '$': {}",
+            path
+        )));
+    }
+
+    let mut segments = Vec::new();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '.' => {
+                chars.next();
+                if chars.peek() == Some(&'*') {
+                    chars.next();
+                    segments.push(Segment::Wildcard);
+                } else {
+                    let name = take_while(&mut chars, |c| c != '.' && c != '[');
+                    if name.is_empty() {
+                        return Err(Error::InvalidPath(format!(
+                            "empty child name in: {}",
+                            path
+                        )));
+                    }
+                    segments.push(Segment::Child(name));
+                }
+            }
+            '[' => {
+                chars.next();
+                let inner = take_while(&mut chars, |c| c != ']');
+                if chars.next() != Some(']') {
+                    return Err(Error::InvalidPath(format!(
+                        "unterminated '[' in: {}",
+                        path
+                    )));
+                }
+                segments.push(parse_bracket(&inner, path)?);
+            }
+            _ => {
+                return Err(Error::InvalidPath(format!(
+                    "unexpected character '{}' in: {}",
+                    c, path
+                )))
+            }
+        }
+    }
+    Ok(segments)
+}
+
+fn take_while(
+    chars: &mut std::iter::Peekable<std::str::Chars>,
+    pred: impl Fn(char) -> bool,
+) -> String {
+    let mut s = String::new();
+    while let Some(&c) = chars.peek() {
+        if !pred(c) {
+            break;
+        }
+        s.push(c);
+        chars.next();
+    }
+    s
+}
+
+fn parse_bracket(inner: &str, path: &str) -> Result<Segment> {
+    let inner = inner.trim();
+    if inner == "*" {
+        return Ok(Segment::Wildcard);
+    }
+    let quoted = (inner.starts_with('\'') && inner.ends_with('\''))
+        || (inner.starts_with('"') && inner.ends_with('"'));
+    if quoted && inner.len() >= 2 {
+        return Ok(Segment::Child(inner[1..inner.len() - 1].to_string()));
+    }
+    if let Some(colon) = inner.find(':') {
+        let (start, end) = inner.split_at(colon);
+        let end = &end[1..];
+        let start = parse_slice_bound(start, path)?;
+        let end = parse_slice_bound(end, path)?;
+        return Ok(Segment::Slice(start, end));
+    }
+    inner
+        .parse::<usize>()
+        .map(Segment::Index)
+        .map_err(|_| Error::InvalidPath(format!("bad index '{}' in: {}", inner, path)))
+}
+
+fn parse_slice_bound(s: &str, path: &str) -> Result<Option<isize>> {
+    if s.is_empty() {
+        return Ok(None);
+    }
+    s.parse::<isize>()
+        .map(Some)
+        .map_err(|_| Error::InvalidPath(format!("bad slice bound '{}' in: {}", s, path)))
+}
+
+fn slice_bounds(len: usize, start: Option<isize>, end: Option<isize>) -> (usize, usize) {
+    let resolve = |i: isize| -> usize {
+        if i < 0 {
+            len.saturating_sub((-i) as usize)
+        } else {
+            (i as usize).min(len)
+        }
+    };
+    let s = start.map(resolve).unwrap_or(0);
+    let e = end.map(resolve).unwrap_or(len);
+    if e < s {
+        (s, s)
+    } else {
+        (s, e)
+    }
+}
+
+/// Return every node matching `segments`, in document order.
+pub(crate) fn query<'v>(root: &'v Value, segments: &[Segment]) -> Vec<&'v Value> {
+    let mut current = vec![root];
+    for segment in segments {
+        let mut next = Vec::new();
+        for value in current {
+            match segment {
+                Segment::Child(name) => {
+                    if let Some(v) = value.as_object().and_then(|m| m.get(name)) {
+                        next.push(v);
+                    }
+                }
+                Segment::Index(i) => {
+                    if let Some(v) = value.as_array().and_then(|a| a.get(*i)) {
+                        next.push(v);
+                    }
+                }
+                Segment::Wildcard => match value {
+                    Value::Object(map) => next.extend(map.values()),
+                    Value::Array(arr) => next.extend(arr.iter()),
+                    _ => {}
+                },
+                Segment::Slice(start, end) => {
+                    if let Some(arr) = value.as_array() {
+                        let (s, e) = slice_bounds(arr.len(), *start, *end);
+                        next.extend(arr[s..e].iter());
+                    }
+                }
+            }
+        }
+        current = next;
+    }
+    current
+}
+
+/// Set `value` at every node matching `segments`, auto-vivifying missing
+/// intermediate objects/arrays. Errors if a segment cannot be satisfied
+/// against the existing shape of the tree (e.g. indexing into a string).
+pub(crate) fn set(root: &mut Value, segments: &[Segment], value: Value) -> Result<()> {
+    set_rec(root, segments, value)
+}
+
+fn set_rec(current: &mut Value, segments: &[Segment], value: Value) -> Result<()> {
+    let (segment, rest) = match segments.split_first() {
+        Some(pair) => pair,
+        None => {
+            *current = value;
+            return Ok(());
+        }
+    };
+
+    match segment {
+        Segment::Child(name) => {
+            if current.is_null() {
+                *current = Value::Object(Map::new());
+            }
+            let map = current.as_object_mut().ok_or_else(|| {
+                Error::PathMismatch(format!("cannot set child '{}' on non-object value", name))
+            })?;
+            let entry = map.entry(name.clone()).or_insert(Value::Null);
+            set_rec(entry, rest, value)
+        }
+        Segment::Index(i) => {
+            if current.is_null() {
+                *current = Value::Array(Vec::new());
+            }
+            let arr = current.as_array_mut().ok_or_else(|| {
+                Error::PathMismatch(format!("cannot set index {} on non-array value", i))
+            })?;
+            if *i >= arr.len() {
+                arr.resize(*i + 1, Value::Null);
+            }
+            set_rec(&mut arr[*i], rest, value)
+        }
+        Segment::Wildcard => match current {
+            Value::Object(map) => {
+                for key in map.keys().cloned().collect::<Vec<_>>() {
+                    set_rec(map.get_mut(&key).unwrap(), rest, value.clone())?;
+                }
+                Ok(())
+            }
+            Value::Array(arr) => {
+                for entry in arr.iter_mut() {
+                    set_rec(entry, rest, value.clone())?;
+                }
+                Ok(())
+            }
+            _ => Err(Error::PathMismatch(
+                "wildcard segment requires an existing object or array".to_string(),
+            )),
+        },
+        Segment::Slice(start, end) => {
+            let arr = current
+                .as_array_mut()
+                .ok_or_else(|| Error::PathMismatch("slice segment requires an array".to_string()))?;
+            let (s, e) = slice_bounds(arr.len(), *start, *end);
+            for entry in arr[s..e].iter_mut() {
+                set_rec(entry, rest, value.clone())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_dotted_and_bracketed_child_access() {
+        assert_eq!(
+            parse("$.user.addresses[0].city").unwrap(),
+            vec![
+                Segment::Child("user".to_string()),
+                Segment::Child("addresses".to_string()),
+                Segment::Index(0),
+                Segment::Child("city".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_wildcard_and_slice() {
+        assert_eq!(
+            parse("$.items[*].price").unwrap(),
+            vec![
+                Segment::Child("items".to_string()),
+                Segment::Wildcard,
+                Segment::Child("price".to_string()),
+            ]
+        );
+        assert_eq!(parse("$[1:3]").unwrap(), vec![Segment::Slice(Some(1), Some(3))]);
+    }
+
+    #[test]
+    fn rejects_paths_not_starting_with_root() {
+        assert!(parse("user.name").is_err());
+    }
+
+    #[test]
+    fn queries_matching_nodes() {
+        let value = json!({"items": [{"price": 1}, {"price": 2}]});
+        let segments = parse("$.items[*].price").unwrap();
+        let matches: Vec<&Value> = query(&value, &segments);
+        assert_eq!(matches, vec![&json!(1), &json!(2)]);
+    }
+
+    #[test]
+    fn set_auto_vivifies_missing_intermediates() {
+        let mut value = Value::Null;
+        let segments = parse("$.user.name").unwrap();
+        set(&mut value, &segments, json!("Ada")).unwrap();
+        assert_eq!(value, json!({"user": {"name": "Ada"}}));
+    }
+
+    #[test]
+    fn set_errors_on_shape_mismatch() {
+        let mut value = json!("not an object");
+        let segments = parse("$.name").unwrap();
+        assert!(set(&mut value, &segments, json!("Ada")).is_err());
+    }
+
+    #[test]
+    fn set_root_replaces_whole_value() {
+        let mut value = json!({"a": 1});
+        set(&mut value, &[], json!(5)).unwrap();
+        assert_eq!(value, json!(5));
+    }
+}
@@ -0,0 +1,427 @@
+//! Deterministic generation of JTD-conforming instances from a byte seed,
+//! for building large fixtures and fuzz corpus seeds. Consumes bytes the
+//! same way `fuzz/fuzz_targets/json_fuzzer.rs` does: a byte to pick a
+//! variant/mapping, a byte (mod a small bound) for array/map lengths, and
+//! bytes to fill primitive leaves.
+
+use serde_json::{Map, Value};
+
+use super::{PrimitiveType, Schema};
+
+const MAX_DEPTH: usize = 8;
+const LEN_BOUND: usize = 5;
+const STRING_LEN_BOUND: usize = 8;
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    index: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Cursor { data, index: 0 }
+    }
+
+    fn exhausted(&self) -> bool {
+        self.index >= self.data.len()
+    }
+
+    fn next_byte(&mut self) -> u8 {
+        if self.exhausted() {
+            return 0;
+        }
+        let byte = self.data[self.index];
+        self.index += 1;
+        byte
+    }
+
+    fn next_bool(&mut self) -> bool {
+        self.next_byte() & 1 == 1
+    }
+
+    fn next_len(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            0
+        } else {
+            self.next_byte() as usize % bound
+        }
+    }
+
+    fn next_i64(&mut self, width: usize) -> i64 {
+        let mut buf = [0u8; 8];
+        for slot in buf.iter_mut().take(width) {
+            *slot = self.next_byte();
+        }
+        i64::from_le_bytes(buf)
+    }
+
+    fn next_string(&mut self, len: usize) -> String {
+        (0..len)
+            .map(|_| ALPHABET[self.next_byte() as usize % ALPHABET.len()] as char)
+            .collect()
+    }
+
+    fn next_timestamp(&mut self) -> String {
+        let year = 2000 + (self.next_byte() as u32 % 50);
+        let month = 1 + (self.next_byte() as u32 % 12);
+        let day = 1 + (self.next_byte() as u32 % 28);
+        let hour = self.next_byte() as u32 % 24;
+        let minute = self.next_byte() as u32 % 60;
+        let second = self.next_byte() as u32 % 60;
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            year, month, day, hour, minute, second
+        )
+    }
+}
+
+/// Deterministically produce a JSON instance conforming to `schema`,
+/// consuming bytes from `seed`. The same `(schema, seed)` pair always
+/// produces the same instance, and the result always validates against
+/// `schema` via [`super::validate`].
+pub fn fuzz(schema: &Schema, seed: &[u8]) -> Value {
+    let mut cursor = Cursor::new(seed);
+    fuzz_schema(schema, schema, &mut cursor, 0)
+}
+
+fn fuzz_schema(root: &Schema, schema: &Schema, cursor: &mut Cursor, depth: usize) -> Value {
+    if let Some(reference) = &schema.reference {
+        return match root.definitions.get(reference) {
+            Some(target) if depth < MAX_DEPTH && !cursor.exhausted() => {
+                fuzz_schema(root, target, cursor, depth + 1)
+            }
+            Some(target) => minimal_conforming(target),
+            None => Value::Null,
+        };
+    }
+
+    if let Some(type_) = schema.type_ {
+        return fuzz_primitive(type_, cursor);
+    }
+
+    if let Some(values) = &schema.enum_ {
+        return match values.first() {
+            Some(_) => Value::String(values[cursor.next_byte() as usize % values.len()].clone()),
+            None => Value::Null,
+        };
+    }
+
+    if let Some(sub) = &schema.elements {
+        let len = cursor.next_len(LEN_BOUND);
+        let items = (0..len)
+            .map(|_| fuzz_schema(root, sub, cursor, depth + 1))
+            .collect();
+        return Value::Array(items);
+    }
+
+    if schema.properties.is_some() || schema.optional_properties.is_some() {
+        return fuzz_properties(root, schema, cursor, depth);
+    }
+
+    if let Some(sub) = &schema.values {
+        let len = cursor.next_len(LEN_BOUND);
+        let mut map = Map::new();
+        for i in 0..len {
+            map.insert(format!("key{}", i), fuzz_schema(root, sub, cursor, depth + 1));
+        }
+        return Value::Object(map);
+    }
+
+    if let Some(tag) = &schema.discriminator {
+        return fuzz_discriminator(root, schema, tag, cursor, depth);
+    }
+
+    Value::Null
+}
+
+fn fuzz_primitive(type_: PrimitiveType, cursor: &mut Cursor) -> Value {
+    match type_ {
+        PrimitiveType::Boolean => Value::Bool(cursor.next_bool()),
+        PrimitiveType::Float32 | PrimitiveType::Float64 => {
+            Value::from(cursor.next_i64(4) as f64 / 1000.0)
+        }
+        PrimitiveType::Int8 => Value::from(cursor.next_i64(1) as i8),
+        PrimitiveType::Int16 => Value::from(cursor.next_i64(2) as i16),
+        PrimitiveType::Int32 => Value::from(cursor.next_i64(4) as i32),
+        PrimitiveType::Uint8 => Value::from(cursor.next_byte()),
+        PrimitiveType::Uint16 => Value::from(cursor.next_i64(2) as u16),
+        PrimitiveType::Uint32 => Value::from(cursor.next_i64(4) as u32),
+        PrimitiveType::String => {
+            let len = cursor.next_len(STRING_LEN_BOUND);
+            Value::String(cursor.next_string(len))
+        }
+        PrimitiveType::Timestamp => Value::String(cursor.next_timestamp()),
+    }
+}
+
+fn fuzz_properties(root: &Schema, schema: &Schema, cursor: &mut Cursor, depth: usize) -> Value {
+    let mut map = Map::new();
+    if let Some(required) = &schema.properties {
+        for (key, sub) in required {
+            map.insert(key.clone(), fuzz_schema(root, sub, cursor, depth + 1));
+        }
+    }
+    if let Some(optional) = &schema.optional_properties {
+        for (key, sub) in optional {
+            if cursor.next_bool() {
+                map.insert(key.clone(), fuzz_schema(root, sub, cursor, depth + 1));
+            }
+        }
+    }
+    Value::Object(map)
+}
+
+fn fuzz_discriminator(
+    root: &Schema,
+    schema: &Schema,
+    tag: &str,
+    cursor: &mut Cursor,
+    depth: usize,
+) -> Value {
+    let mapping = match &schema.mapping {
+        Some(mapping) if !mapping.is_empty() => mapping,
+        _ => return Value::Object(Map::new()),
+    };
+
+    let keys: Vec<&String> = mapping.keys().collect();
+    let tag_value = keys[cursor.next_byte() as usize % keys.len()].clone();
+    let sub = &mapping[&tag_value];
+
+    let mut value = fuzz_schema(root, sub, cursor, depth + 1);
+    if let Value::Object(map) = &mut value {
+        map.insert(tag.to_string(), Value::String(tag_value));
+    }
+    value
+}
+
+/// The smallest instance that conforms to `schema`, used as the fallback
+/// once the seed is exhausted or the recursion-depth guard trips.
+fn minimal_conforming(schema: &Schema) -> Value {
+    if let Some(type_) = schema.type_ {
+        return match type_ {
+            PrimitiveType::Boolean => Value::Bool(false),
+            PrimitiveType::String => Value::String(String::new()),
+            PrimitiveType::Timestamp => Value::String("1970-01-01T00:00:00Z".to_string()),
+            _ => Value::from(0),
+        };
+    }
+    if let Some(first) = schema.enum_.as_ref().and_then(|v| v.first()) {
+        return Value::String(first.clone());
+    }
+    if schema.elements.is_some() {
+        return Value::Array(Vec::new());
+    }
+    if schema.properties.is_some() || schema.optional_properties.is_some() {
+        let mut map = Map::new();
+        if let Some(required) = &schema.properties {
+            for (key, sub) in required {
+                map.insert(key.clone(), minimal_conforming(sub));
+            }
+        }
+        return Value::Object(map);
+    }
+    if schema.values.is_some() {
+        return Value::Object(Map::new());
+    }
+    if let Some(tag) = &schema.discriminator {
+        if let Some((tag_value, sub)) = schema.mapping.as_ref().and_then(|m| m.iter().next()) {
+            let mut value = minimal_conforming(sub);
+            if let Value::Object(map) = &mut value {
+                map.insert(tag.clone(), Value::String(tag_value.clone()));
+            }
+            return value;
+        }
+        return Value::Object(Map::new());
+    }
+    Value::Null
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::validate;
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn assert_round_trips(schema: &Schema) {
+        for seed in [
+            &b""[..],
+            &b"\x00"[..],
+            &[1, 2, 3, 4, 5, 6, 7, 8][..],
+            &[255, 254, 253, 252, 251, 250, 249, 248, 247, 246][..],
+        ] {
+            let instance = fuzz(schema, seed);
+            let errors = validate(schema, &instance);
+            assert!(
+                errors.is_empty(),
+                "seed {:?} produced non-conforming instance {:?}: {:?}",
+                seed,
+                instance,
+                errors
+            );
+        }
+    }
+
+    #[test]
+    fn round_trips_empty_form() {
+        assert_round_trips(&Schema::default());
+    }
+
+    #[test]
+    fn round_trips_type_form() {
+        for type_ in [
+            PrimitiveType::Boolean,
+            PrimitiveType::Float32,
+            PrimitiveType::Int8,
+            PrimitiveType::Uint32,
+            PrimitiveType::String,
+            PrimitiveType::Timestamp,
+        ] {
+            assert_round_trips(&Schema {
+                type_: Some(type_),
+                ..Default::default()
+            });
+        }
+    }
+
+    #[test]
+    fn round_trips_enum_form() {
+        assert_round_trips(&Schema {
+            enum_: Some(vec!["a".to_string(), "b".to_string()]),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn round_trips_elements_form() {
+        assert_round_trips(&Schema {
+            elements: Some(Box::new(Schema {
+                type_: Some(PrimitiveType::Int32),
+                ..Default::default()
+            })),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn round_trips_properties_form() {
+        let mut required = BTreeMap::new();
+        required.insert(
+            "name".to_string(),
+            Schema {
+                type_: Some(PrimitiveType::String),
+                ..Default::default()
+            },
+        );
+        let mut optional = BTreeMap::new();
+        optional.insert(
+            "age".to_string(),
+            Schema {
+                type_: Some(PrimitiveType::Uint8),
+                ..Default::default()
+            },
+        );
+        assert_round_trips(&Schema {
+            properties: Some(required),
+            optional_properties: Some(optional),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn round_trips_values_form() {
+        assert_round_trips(&Schema {
+            values: Some(Box::new(Schema {
+                type_: Some(PrimitiveType::Boolean),
+                ..Default::default()
+            })),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn round_trips_discriminator_form() {
+        let mut a_props = BTreeMap::new();
+        a_props.insert(
+            "a_field".to_string(),
+            Schema {
+                type_: Some(PrimitiveType::String),
+                ..Default::default()
+            },
+        );
+        let mut b_props = BTreeMap::new();
+        b_props.insert(
+            "b_field".to_string(),
+            Schema {
+                type_: Some(PrimitiveType::Int32),
+                ..Default::default()
+            },
+        );
+        let mut mapping = BTreeMap::new();
+        mapping.insert(
+            "a".to_string(),
+            Schema {
+                properties: Some(a_props),
+                ..Default::default()
+            },
+        );
+        mapping.insert(
+            "b".to_string(),
+            Schema {
+                properties: Some(b_props),
+                ..Default::default()
+            },
+        );
+        assert_round_trips(&Schema {
+            discriminator: Some("kind".to_string()),
+            mapping: Some(mapping),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn round_trips_ref_form() {
+        let mut definitions = BTreeMap::new();
+        definitions.insert(
+            "id".to_string(),
+            Schema {
+                type_: Some(PrimitiveType::String),
+                ..Default::default()
+            },
+        );
+        assert_round_trips(&Schema {
+            definitions,
+            reference: Some("id".to_string()),
+            ..Default::default()
+        });
+    }
+
+    #[test]
+    fn minimal_conforming_discriminator_includes_tag() {
+        let mut a_props = BTreeMap::new();
+        a_props.insert(
+            "a_field".to_string(),
+            Schema {
+                type_: Some(PrimitiveType::String),
+                ..Default::default()
+            },
+        );
+        let mut mapping = BTreeMap::new();
+        mapping.insert(
+            "a".to_string(),
+            Schema {
+                properties: Some(a_props),
+                ..Default::default()
+            },
+        );
+        let schema = Schema {
+            discriminator: Some("kind".to_string()),
+            mapping: Some(mapping),
+            ..Default::default()
+        };
+
+        let instance = minimal_conforming(&schema);
+        assert!(validate(&schema, &instance).is_empty());
+    }
+}
+
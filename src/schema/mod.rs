@@ -0,0 +1,522 @@
+//! Validation of builder output against a [JSON Type Definition][jtd] (JTD,
+//! RFC 8927) schema.
+//!
+//! [jtd]: https://datatracker.ietf.org/doc/html/rfc8927
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::ObjectBuilder;
+
+mod fuzz;
+
+pub use fuzz::fuzz;
+
+/// The primitive types usable in a JTD `type` form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrimitiveType {
+    Boolean,
+    Float32,
+    Float64,
+    Int8,
+    Int16,
+    Int32,
+    Uint8,
+    Uint16,
+    Uint32,
+    String,
+    Timestamp,
+}
+
+/// A JSON Type Definition schema. Exactly one of the optional fields (beyond
+/// `definitions`) should be set, selecting which of the eight JTD forms the
+/// schema takes: *empty* (none set), *type*, *enum*, *elements*,
+/// *properties*, *values*, *discriminator*, or *ref*.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Schema {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub definitions: BTreeMap<String, Schema>,
+
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub type_: Option<PrimitiveType>,
+
+    #[serde(rename = "enum", default, skip_serializing_if = "Option::is_none")]
+    pub enum_: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub elements: Option<Box<Schema>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub properties: Option<BTreeMap<String, Schema>>,
+
+    #[serde(
+        rename = "optionalProperties",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub optional_properties: Option<BTreeMap<String, Schema>>,
+
+    #[serde(
+        rename = "additionalProperties",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub additional_properties: Option<bool>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Box<Schema>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<BTreeMap<String, Schema>>,
+}
+
+/// A single validation failure, carrying the path into the instance that
+/// failed and the path into the schema that rejected it, JTD-style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub instance_path: Vec<String>,
+    pub schema_path: Vec<String>,
+}
+
+/// Validate `instance` against `schema`, returning every mismatch found.
+/// An empty result means `instance` conforms.
+pub fn validate(schema: &Schema, instance: &Value) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    validate_into(schema, schema, instance, &[], &[], &mut errors);
+    errors
+}
+
+/// Build an object with `f` and validate the result against `schema` in one
+/// step, returning the validation errors instead of the value on failure.
+pub fn build_and_validate<F: FnOnce(&mut ObjectBuilder)>(
+    schema: &Schema,
+    f: F,
+) -> Result<Value, Vec<ValidationError>> {
+    let value = crate::object(f).unwrap();
+    let errors = validate(schema, &value);
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+fn push_error(instance_path: &[String], schema_path: &[String], errors: &mut Vec<ValidationError>) {
+    errors.push(ValidationError {
+        instance_path: instance_path.to_vec(),
+        schema_path: schema_path.to_vec(),
+    });
+}
+
+fn joined(path: &[String], segment: impl Into<String>) -> Vec<String> {
+    let mut next = path.to_vec();
+    next.push(segment.into());
+    next
+}
+
+fn validate_into(
+    root: &Schema,
+    schema: &Schema,
+    instance: &Value,
+    instance_path: &[String],
+    schema_path: &[String],
+    errors: &mut Vec<ValidationError>,
+) {
+    if let Some(reference) = &schema.reference {
+        let ref_path = joined(schema_path, "ref");
+        match root.definitions.get(reference) {
+            Some(target) => validate_into(root, target, instance, instance_path, &ref_path, errors),
+            None => push_error(instance_path, &ref_path, errors),
+        }
+    } else if let Some(type_) = schema.type_ {
+        validate_type(type_, instance, instance_path, schema_path, errors);
+    } else if let Some(values) = &schema.enum_ {
+        let ok = instance
+            .as_str()
+            .map(|s| values.iter().any(|v| v == s))
+            .unwrap_or(false);
+        if !ok {
+            push_error(instance_path, &joined(schema_path, "enum"), errors);
+        }
+    } else if let Some(sub) = &schema.elements {
+        let elements_path = joined(schema_path, "elements");
+        match instance.as_array() {
+            Some(arr) => {
+                for (i, item) in arr.iter().enumerate() {
+                    validate_into(
+                        root,
+                        sub,
+                        item,
+                        &joined(instance_path, i.to_string()),
+                        &elements_path,
+                        errors,
+                    );
+                }
+            }
+            None => push_error(instance_path, &elements_path, errors),
+        }
+    } else if schema.properties.is_some() || schema.optional_properties.is_some() {
+        validate_properties(root, schema, instance, instance_path, schema_path, errors, None);
+    } else if let Some(sub) = &schema.values {
+        let values_path = joined(schema_path, "values");
+        match instance.as_object() {
+            Some(obj) => {
+                for (key, value) in obj {
+                    validate_into(
+                        root,
+                        sub,
+                        value,
+                        &joined(instance_path, key.clone()),
+                        &values_path,
+                        errors,
+                    );
+                }
+            }
+            None => push_error(instance_path, &values_path, errors),
+        }
+    } else if let Some(tag) = &schema.discriminator {
+        validate_discriminator(root, schema, tag, instance, instance_path, schema_path, errors);
+    }
+    // Otherwise this is the empty form, which matches any instance.
+}
+
+fn validate_type(
+    type_: PrimitiveType,
+    instance: &Value,
+    instance_path: &[String],
+    schema_path: &[String],
+    errors: &mut Vec<ValidationError>,
+) {
+    let ok = match type_ {
+        PrimitiveType::Boolean => instance.is_boolean(),
+        PrimitiveType::Float32 | PrimitiveType::Float64 => instance.is_number(),
+        PrimitiveType::Int8 => fits_integer(instance, i8::MIN as i64, i8::MAX as i64),
+        PrimitiveType::Int16 => fits_integer(instance, i16::MIN as i64, i16::MAX as i64),
+        PrimitiveType::Int32 => fits_integer(instance, i32::MIN as i64, i32::MAX as i64),
+        PrimitiveType::Uint8 => fits_integer(instance, 0, u8::MAX as i64),
+        PrimitiveType::Uint16 => fits_integer(instance, 0, u16::MAX as i64),
+        PrimitiveType::Uint32 => fits_integer(instance, 0, u32::MAX as i64),
+        PrimitiveType::String => instance.is_string(),
+        PrimitiveType::Timestamp => instance.as_str().map(is_rfc3339).unwrap_or(false),
+    };
+    if !ok {
+        push_error(instance_path, &joined(schema_path, "type"), errors);
+    }
+}
+
+fn fits_integer(instance: &Value, min: i64, max: i64) -> bool {
+    instance.as_i64().map(|n| n >= min && n <= max).unwrap_or(false)
+}
+
+/// A lightweight structural check for RFC 3339 timestamps, good enough to
+/// catch malformed input without pulling in a date/time dependency.
+fn is_rfc3339(s: &str) -> bool {
+    let bytes = s.as_bytes();
+    if bytes.len() < 20 {
+        return false;
+    }
+    let digits = |range: std::ops::Range<usize>| bytes[range].iter().all(u8::is_ascii_digit);
+    digits(0..4)
+        && bytes[4] == b'-'
+        && digits(5..7)
+        && bytes[7] == b'-'
+        && digits(8..10)
+        && (bytes[10] == b'T' || bytes[10] == b't')
+        && digits(11..13)
+        && bytes[13] == b':'
+        && digits(14..16)
+        && bytes[16] == b':'
+        && digits(17..19)
+        && matches!(bytes[19], b'Z' | b'z' | b'.' | b'+' | b'-')
+}
+
+fn validate_properties(
+    root: &Schema,
+    schema: &Schema,
+    instance: &Value,
+    instance_path: &[String],
+    schema_path: &[String],
+    errors: &mut Vec<ValidationError>,
+    // The discriminator tag key, when `schema` is itself a mapped schema
+    // being validated as part of a discriminator form: RFC 8927 requires
+    // the tag to be implicitly allowed even though the mapped schema never
+    // lists it in `properties`/`optionalProperties`.
+    implicitly_allowed_key: Option<&str>,
+) {
+    let obj = match instance.as_object() {
+        Some(obj) => obj,
+        None => {
+            push_error(instance_path, &joined(schema_path, "properties"), errors);
+            return;
+        }
+    };
+
+    let empty = BTreeMap::new();
+    let required = schema.properties.as_ref().unwrap_or(&empty);
+    let optional = schema.optional_properties.as_ref().unwrap_or(&empty);
+
+    for (key, sub) in required {
+        let prop_schema_path = joined(&joined(schema_path, "properties"), key.clone());
+        match obj.get(key) {
+            Some(value) => validate_into(
+                root,
+                sub,
+                value,
+                &joined(instance_path, key.clone()),
+                &prop_schema_path,
+                errors,
+            ),
+            None => push_error(instance_path, &prop_schema_path, errors),
+        }
+    }
+
+    for (key, sub) in optional {
+        if let Some(value) = obj.get(key) {
+            let prop_schema_path = joined(&joined(schema_path, "optionalProperties"), key.clone());
+            validate_into(
+                root,
+                sub,
+                value,
+                &joined(instance_path, key.clone()),
+                &prop_schema_path,
+                errors,
+            );
+        }
+    }
+
+    if !schema.additional_properties.unwrap_or(false) {
+        for key in obj.keys() {
+            let is_known = required.contains_key(key)
+                || optional.contains_key(key)
+                || implicitly_allowed_key == Some(key.as_str());
+            if !is_known {
+                push_error(&joined(instance_path, key.clone()), schema_path, errors);
+            }
+        }
+    }
+}
+
+fn validate_discriminator(
+    root: &Schema,
+    schema: &Schema,
+    tag: &str,
+    instance: &Value,
+    instance_path: &[String],
+    schema_path: &[String],
+    errors: &mut Vec<ValidationError>,
+) {
+    let obj = match instance.as_object() {
+        Some(obj) => obj,
+        None => {
+            push_error(instance_path, &joined(schema_path, "discriminator"), errors);
+            return;
+        }
+    };
+
+    let tag_path = joined(instance_path, tag.to_string());
+    let tag_value = match obj.get(tag).and_then(Value::as_str) {
+        Some(value) => value,
+        None => {
+            push_error(&tag_path, &joined(schema_path, "discriminator"), errors);
+            return;
+        }
+    };
+
+    let mapping_path = joined(schema_path, "mapping");
+    match schema.mapping.as_ref().and_then(|m| m.get(tag_value)) {
+        Some(sub) => {
+            let sub_schema_path = joined(&mapping_path, tag_value.to_string());
+            if sub.properties.is_some() || sub.optional_properties.is_some() {
+                // Per RFC 8927, the tag itself is never listed in the mapped
+                // schema's own `properties`/`optionalProperties`, so it must
+                // be treated as implicitly allowed here rather than flagged
+                // as an unknown additional property.
+                validate_properties(
+                    root,
+                    sub,
+                    instance,
+                    instance_path,
+                    &sub_schema_path,
+                    errors,
+                    Some(tag),
+                );
+            } else {
+                validate_into(root, sub, instance, instance_path, &sub_schema_path, errors);
+            }
+        }
+        None => push_error(&tag_path, &mapping_path, errors),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn user_schema() -> Schema {
+        Schema {
+            properties: Some(BTreeMap::from([(
+                "name".to_string(),
+                Schema {
+                    type_: Some(PrimitiveType::String),
+                    ..Default::default()
+                },
+            )])),
+            optional_properties: Some(BTreeMap::from([(
+                "age".to_string(),
+                Schema {
+                    type_: Some(PrimitiveType::Uint8),
+                    ..Default::default()
+                },
+            )])),
+            additional_properties: Some(false),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn validate_properties_accepts_conforming_instance() {
+        let schema = user_schema();
+        let instance = json!({"name": "Ada", "age": 30});
+        assert_eq!(validate(&schema, &instance), Vec::new());
+    }
+
+    #[test]
+    fn validate_properties_rejects_wrong_type() {
+        let schema = user_schema();
+        let instance = json!({"name": 5});
+        let errors = validate(&schema, &instance);
+        assert_eq!(
+            errors,
+            vec![ValidationError {
+                instance_path: vec!["name".to_string()],
+                schema_path: vec!["properties".to_string(), "name".to_string(), "type".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn validate_properties_rejects_missing_required_key() {
+        let schema = user_schema();
+        let instance = json!({});
+        let errors = validate(&schema, &instance);
+        assert_eq!(
+            errors,
+            vec![ValidationError {
+                instance_path: Vec::new(),
+                schema_path: vec!["properties".to_string(), "name".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn validate_properties_rejects_unexpected_key() {
+        let schema = user_schema();
+        let instance = json!({"name": "Ada", "extra": 1});
+        let errors = validate(&schema, &instance);
+        assert_eq!(
+            errors,
+            vec![ValidationError {
+                instance_path: vec!["extra".to_string()],
+                schema_path: Vec::new(),
+            }]
+        );
+    }
+
+    fn event_schema() -> Schema {
+        Schema {
+            discriminator: Some("kind".to_string()),
+            mapping: Some(BTreeMap::from([(
+                "click".to_string(),
+                Schema {
+                    properties: Some(BTreeMap::from([(
+                        "x".to_string(),
+                        Schema {
+                            type_: Some(PrimitiveType::Int32),
+                            ..Default::default()
+                        },
+                    )])),
+                    additional_properties: Some(false),
+                    ..Default::default()
+                },
+            )])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn validate_discriminator_exempts_tag_but_still_rejects_unknown_keys() {
+        let schema = event_schema();
+
+        // The tag itself isn't listed in the mapped schema's `properties`,
+        // but must still be accepted rather than flagged as unexpected.
+        let conforming = json!({"kind": "click", "x": 1});
+        assert_eq!(validate(&schema, &conforming), Vec::new());
+
+        // A genuinely unknown key in the same mapped object must still be
+        // rejected -- the tag exemption must not over-permit.
+        let with_unknown_key = json!({"kind": "click", "x": 1, "y": 2});
+        let errors = validate(&schema, &with_unknown_key);
+        assert_eq!(
+            errors,
+            vec![ValidationError {
+                instance_path: vec!["y".to_string()],
+                schema_path: vec!["mapping".to_string(), "click".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn validate_discriminator_rejects_unknown_tag_value() {
+        let schema = event_schema();
+        let instance = json!({"kind": "scroll"});
+        let errors = validate(&schema, &instance);
+        assert_eq!(
+            errors,
+            vec![ValidationError {
+                instance_path: vec!["kind".to_string()],
+                schema_path: vec!["mapping".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn validate_rejects_missing_ref_target() {
+        let schema = Schema {
+            reference: Some("missing".to_string()),
+            ..Default::default()
+        };
+        let errors = validate(&schema, &json!(1));
+        assert_eq!(
+            errors,
+            vec![ValidationError {
+                instance_path: Vec::new(),
+                schema_path: vec!["ref".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn build_and_validate_returns_errors_for_non_conforming_output() {
+        let schema = user_schema();
+        let result = build_and_validate(&schema, |obj| {
+            obj.set("age", 30);
+        });
+        assert_eq!(
+            result,
+            Err(vec![ValidationError {
+                instance_path: Vec::new(),
+                schema_path: vec!["properties".to_string(), "name".to_string()],
+            }])
+        );
+    }
+}
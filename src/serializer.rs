@@ -0,0 +1,119 @@
+use std::io;
+
+use serde_json::Value;
+
+use crate::object_builder::ObjectBuilder;
+
+/// Implemented by types that know how to render themselves as a JSON object
+/// via an [`ObjectBuilder`].
+pub trait Serializer {
+    /// Optional name used to wrap the serialized object, e.g. `{"root": {...}}`.
+    fn root(&self) -> Option<&str> {
+        None
+    }
+
+    /// Populate `json` with this type's fields.
+    fn build(&self, json: &mut ObjectBuilder);
+
+    /// Build the JSON value, wrapping it under [`Serializer::root`] when
+    /// `include_root` is true and a root name is given.
+    fn serialize(&self, include_root: bool) -> Value {
+        let mut builder = ObjectBuilder::new();
+        self.build(&mut builder);
+        let value = builder.unwrap();
+
+        match (include_root, self.root()) {
+            (true, Some(root)) => {
+                let mut wrapper = ObjectBuilder::new();
+                wrapper.set(root, value);
+                wrapper.unwrap()
+            }
+            _ => value,
+        }
+    }
+
+    /// Like [`Serializer::serialize`], but builds straight into a writer
+    /// instead of an owned `Value`: the `ObjectBuilder` passed to `build`
+    /// is itself backed by `w`, so every `set`/`object`/`array` call inside
+    /// `build` emits its JSON tokens immediately rather than accumulating a
+    /// tree. Callers streaming many records should call this once per
+    /// record (or use
+    /// [`ArrayBuilder::write_objects`](crate::ArrayBuilder::write_objects))
+    /// rather than collecting them into one `Vec`/`ArrayBuilder` first, and
+    /// write each line's trailing newline themselves for NDJSON.
+    fn serialize_to_writer<W: io::Write>(&self, w: &mut W, include_root: bool) -> io::Result<()> {
+        match (include_root, self.root()) {
+            (true, Some(root)) => {
+                let mut wrapper = ObjectBuilder::new_stream(w);
+                wrapper.object(root, |inner| self.build(inner));
+                wrapper.finish_stream()
+            }
+            _ => {
+                let mut builder = ObjectBuilder::new_stream(w);
+                self.build(&mut builder);
+                builder.finish_stream()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A writer that fails its `n`th call to `write`, then succeeds
+    /// forever after -- used to check that a stream-mode builder stops
+    /// emitting bytes once an error is recorded, rather than resuming with
+    /// later nested builders that don't know about the earlier failure.
+    struct FailOnce {
+        calls_left: usize,
+        buf: Vec<u8>,
+    }
+
+    impl io::Write for FailOnce {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            if self.calls_left == 0 {
+                return Err(io::Error::other("boom"));
+            }
+            self.calls_left -= 1;
+            self.buf.extend_from_slice(data);
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct Doc;
+
+    impl Serializer for Doc {
+        fn build(&self, json: &mut ObjectBuilder) {
+            json.set("a", 1);
+            json.object("b", |obj| {
+                obj.set("c", 2);
+            });
+        }
+    }
+
+    #[test]
+    fn serialize_to_writer_surfaces_the_first_write_error() {
+        let mut w = FailOnce {
+            calls_left: 1,
+            buf: Vec::new(),
+        };
+        assert!(Doc.serialize_to_writer(&mut w, false).is_err());
+    }
+
+    #[test]
+    fn serialize_to_writer_stops_writing_once_a_nested_builder_errors() {
+        // Fails on the opening '{' of the root object, before the nested
+        // "b" object ever gets a chance to write its own tokens.
+        let mut w = FailOnce {
+            calls_left: 0,
+            buf: Vec::new(),
+        };
+        assert!(Doc.serialize_to_writer(&mut w, false).is_err());
+        assert!(w.buf.is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+//! Low-level token writer shared by [`crate::ObjectBuilder`] and
+//! [`crate::ArrayBuilder`] when they're streaming straight to an
+//! `io::Write` sink instead of collecting a `serde_json::Value` tree.
+//!
+//! Write errors are captured rather than returned immediately, since the
+//! chainable `&mut Self`-returning builder methods (`set`, `push`,
+//! `object`, `array`) have no room for a `Result`; the owning builder's
+//! `finish_stream` surfaces the first one hit.
+
+use std::io;
+
+use serde::Serialize;
+
+pub(crate) struct StreamSink<'w> {
+    writer: &'w mut dyn io::Write,
+    wrote_entry: bool,
+    error: Option<io::Error>,
+}
+
+impl<'w> StreamSink<'w> {
+    pub(crate) fn new(writer: &'w mut dyn io::Write) -> Self {
+        StreamSink {
+            writer,
+            wrote_entry: false,
+            error: None,
+        }
+    }
+
+    /// Reborrow the underlying writer for a nested builder to stream into.
+    pub(crate) fn writer_mut(&mut self) -> &mut dyn io::Write {
+        &mut *self.writer
+    }
+
+    pub(crate) fn raw(&mut self, bytes: &'static [u8]) {
+        if self.error.is_none() {
+            if let Err(e) = self.writer.write_all(bytes) {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    pub(crate) fn json<T: Serialize + ?Sized>(&mut self, value: &T) {
+        if self.error.is_none() {
+            if let Err(e) = serde_json::to_writer(&mut *self.writer, value) {
+                self.error = Some(io::Error::from(e));
+            }
+        }
+    }
+
+    /// Write the comma separating this entry from the previous one, if any.
+    pub(crate) fn separator(&mut self) {
+        if self.wrote_entry {
+            self.raw(b",");
+        }
+        self.wrote_entry = true;
+    }
+
+    /// Write `"key":` ahead of an object entry's value.
+    pub(crate) fn begin_entry(&mut self, key: &str) {
+        self.separator();
+        self.json(key);
+        self.raw(b":");
+    }
+
+    pub(crate) fn set_error(&mut self, error: io::Error) {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+    }
+
+    pub(crate) fn has_error(&self) -> bool {
+        self.error.is_some()
+    }
+
+    pub(crate) fn into_error(self) -> Option<io::Error> {
+        self.error
+    }
+}